@@ -54,12 +54,15 @@ pub struct DracoExtensionValue {
     pub buffer_view: usize,
     #[allow(dead_code)]
     pub attributes: HashMap<String, usize>,
+    #[serde(default)]
+    pub targets: Vec<HashMap<String, usize>>,
 }
 
 #[derive(Debug, Default)]
 pub struct DracoSemanticLink {
     pub map: BTreeMap<usize, Semantic>,
     pub buffer_view: usize,
+    pub targets: Vec<BTreeMap<usize, Semantic>>,
 }
 
 impl DracoSemanticLink {
@@ -68,9 +71,20 @@ impl DracoSemanticLink {
         for (sematic_str, index) in &value.attributes {
             id.insert(*index, Semantic::checked(sematic_str).unwrap());
         }
+        let targets = value
+            .targets
+            .iter()
+            .map(|attributes| {
+                attributes
+                    .iter()
+                    .map(|(sematic_str, index)| (*index, Semantic::checked(sematic_str).unwrap()))
+                    .collect()
+            })
+            .collect();
         Self {
             map: id,
             buffer_view: value.buffer_view,
+            targets,
         }
     }
 }
@@ -108,6 +122,7 @@ impl DracoExtension {
         &self,
         primitive: &Primitive,
         decode_config: &DracoDecodeConfig,
+        buffer_data: &[Vec<u8>],
     ) -> Option<Document> {
         let buffer_length = decode_config.estimate_buffer_size();
         let mut root = gltf::json::Root::default();
@@ -129,14 +144,8 @@ impl DracoExtension {
             target: Some(Valid(gltf::json::buffer::Target::ArrayBuffer)),
         });
 
-        // fix when index below u32
         let indices = primitive.indices().unwrap();
-        let data_type = match (indices.data_type(), indices.count()) {
-            (gltf::accessor::DataType::U16, count) if count > u16::MAX as usize => {
-                gltf::accessor::DataType::U32
-            }
-            (data_type, _) => data_type,
-        };
+        let data_type = promoted_index_data_type(&indices);
 
         let indices_accessor = root.push(gltf::json::Accessor {
             buffer_view: Some(indices_index),
@@ -153,49 +162,117 @@ impl DracoExtension {
             sparse: None,
         });
 
+        let attributes = decode_config.attributes();
+
         let mut map = BTreeMap::new();
-        for (index, mesh_attribute) in decode_config.attributes().iter().enumerate() {
-            let semantic = self.link.map.get(&index).unwrap();
+        for (index, semantic) in &self.link.map {
+            let mesh_attribute = &attributes[*index];
             let old_attr = primitive
                 .get(semantic)
                 .unwrap_or_else(|| panic!("can not get accessor by {:?}", semantic));
-            let view_index = root.push(gltf::json::buffer::View {
-                buffer,
-                byte_length: USize64::from(mesh_attribute.lenght() as u64),
-                byte_offset: Some(USize64::from(mesh_attribute.offset() as u64)),
+            let attr_index = Self::push_attribute(&mut root, buffer, mesh_attribute, &old_attr);
+            map.insert(Valid(semantic.clone()), attr_index);
+        }
+
+        // Same gate as `generate_tangent_buffer`, so a TANGENT accessor is only emitted
+        // when `attach_tangent_buffer` actually supplies buffer 1.
+        let tangent_inputs_f32 = [
+            (Semantic::Positions, gltf::accessor::Dimensions::Vec3),
+            (Semantic::Normals, gltf::accessor::Dimensions::Vec3),
+            (Semantic::TexCoords(0), gltf::accessor::Dimensions::Vec2),
+        ]
+        .iter()
+        .all(|(semantic, dimensions)| {
+            primitive.get(semantic).is_some_and(|accessor| {
+                accessor.data_type() == gltf::accessor::DataType::F32
+                    && accessor.dimensions() == *dimensions
+            })
+        });
+        if self.tangent_attribute_ids().is_some() && tangent_inputs_f32 {
+            let vertex_count = primitive
+                .get(&Semantic::Positions)
+                .unwrap_or_else(|| panic!("can not get accessor by {:?}", Semantic::Positions))
+                .count();
+            let tangent_byte_length = USize64::from((vertex_count * 16) as u64);
+            let tangent_buffer = root.push(gltf::json::Buffer {
+                byte_length: tangent_byte_length,
+                extensions: Default::default(),
+                extras: Default::default(),
+                name: None,
+                uri: None,
+            });
+            let tangent_view = root.push(gltf::json::buffer::View {
+                buffer: tangent_buffer,
+                byte_length: tangent_byte_length,
+                byte_offset: Some(USize64::from(0_u64)),
                 byte_stride: None,
                 extensions: Default::default(),
                 extras: Default::default(),
                 name: None,
                 target: Some(Valid(gltf::json::buffer::Target::ArrayBuffer)),
             });
-            let attr_index = root.push(gltf::json::Accessor {
-                buffer_view: Some(view_index),
+            let tangent_accessor = root.push(gltf::json::Accessor {
+                buffer_view: Some(tangent_view),
                 byte_offset: None,
-                count: USize64::from(old_attr.count()),
+                count: USize64::from(vertex_count as u64),
                 component_type: Valid(gltf::json::accessor::GenericComponentType(
-                    old_attr.data_type(),
+                    gltf::accessor::DataType::F32,
                 )),
                 extensions: Default::default(),
                 extras: Default::default(),
-                type_: Valid(old_attr.dimensions()),
-                min: Some(gltf::json::Value::from(old_attr.min())),
-                max: Some(gltf::json::Value::from(old_attr.max())),
+                type_: Valid(gltf::accessor::Dimensions::Vec4),
+                min: None,
+                max: None,
                 name: None,
                 normalized: false,
                 sparse: None,
             });
-            map.insert(Valid(semantic.clone()), attr_index);
+            map.insert(Valid(Semantic::Tangents), tangent_accessor);
         }
 
+        let targets = self
+            .link
+            .targets
+            .iter()
+            .enumerate()
+            .map(|(target_index, target_map)| {
+                let morph_target = primitive
+                    .morph_targets()
+                    .nth(target_index)
+                    .unwrap_or_else(|| panic!("can not get morph target {target_index}"));
+                let mut target_accessors = BTreeMap::new();
+                for (index, semantic) in target_map {
+                    let mesh_attribute = &attributes[*index];
+                    let old_attr = Self::morph_target_accessor(&morph_target, semantic)
+                        .unwrap_or_else(|| {
+                            panic!(
+                                "morph target {target_index} has no accessor for {:?}",
+                                semantic
+                            )
+                        });
+                    let attr_index =
+                        Self::push_attribute(&mut root, buffer, mesh_attribute, &old_attr);
+                    target_accessors.insert(Valid(semantic.clone()), attr_index);
+                }
+                target_accessors
+            })
+            .collect::<Vec<_>>();
+
+        // Copy the material (and the textures/images/samplers it references, except
+        // textures whose image is embedded in a `bufferView`, which `push_image` skips)
+        // into this synthetic `Root` rather than just keeping the source index, so the rebuilt
+        // primitive is self-contained and doesn't depend on the main loader re-resolving
+        // an index that has no meaning against an empty `materials` array here.
+        let material = push_material(&mut root, &primitive.material(), buffer_data);
+
         let primitive_json = gltf::json::mesh::Primitive {
             attributes: map,
             extensions: Default::default(),
             extras: Default::default(),
             indices: Some(indices_accessor),
-            material: None,
+            material,
             mode: Valid(gltf::json::mesh::Mode::Triangles),
-            targets: None,
+            targets: (!targets.is_empty()).then_some(targets),
         };
 
         let _mesh_json = root.push(gltf::json::Mesh {
@@ -211,21 +288,403 @@ impl DracoExtension {
         json.map(Document::from_json_without_validation)
     }
 
-    pub fn decode_mesh(
-        &self,
-        gltf: &Gltf,
-        buffer_data: &Vec<Vec<u8>>,
-    ) -> Option<(DracoDecodeConfig, Vec<Vec<u8>>)> {
+    fn push_attribute(
+        root: &mut gltf::json::Root,
+        buffer: gltf::json::Index<gltf::json::Buffer>,
+        mesh_attribute: &AttributeDataType,
+        old_attr: &gltf::Accessor,
+    ) -> gltf::json::Index<gltf::json::Accessor> {
+        let view_index = root.push(gltf::json::buffer::View {
+            buffer,
+            byte_length: USize64::from(mesh_attribute.lenght() as u64),
+            byte_offset: Some(USize64::from(mesh_attribute.offset() as u64)),
+            byte_stride: None,
+            extensions: Default::default(),
+            extras: Default::default(),
+            name: None,
+            target: Some(Valid(gltf::json::buffer::Target::ArrayBuffer)),
+        });
+        root.push(gltf::json::Accessor {
+            buffer_view: Some(view_index),
+            byte_offset: None,
+            count: USize64::from(old_attr.count()),
+            component_type: Valid(gltf::json::accessor::GenericComponentType(
+                old_attr.data_type(),
+            )),
+            extensions: Default::default(),
+            extras: Default::default(),
+            type_: Valid(old_attr.dimensions()),
+            min: Some(gltf::json::Value::from(old_attr.min())),
+            max: Some(gltf::json::Value::from(old_attr.max())),
+            name: None,
+            normalized: false,
+            sparse: None,
+        })
+    }
+
+    fn morph_target_accessor<'a>(
+        morph_target: &gltf::mesh::MorphTarget<'a>,
+        semantic: &Semantic,
+    ) -> Option<gltf::Accessor<'a>> {
+        match semantic {
+            Semantic::Positions => morph_target.positions(),
+            Semantic::Normals => morph_target.normals(),
+            Semantic::Tangents => morph_target.tangents(),
+            _ => None,
+        }
+    }
+
+    pub fn cache_key(&self, gltf: &Gltf) -> DracoDecodeCacheKey {
         let view = gltf.views().nth(self.link.buffer_view).unwrap();
-        let draco_encode_slice: &[u8] =
-            &buffer_data[view.buffer().index()][view.offset()..view.offset() + view.length()];
-        let result_opt = decode_mesh_with_config_sync(draco_encode_slice);
+        (view.buffer().index(), view.offset(), view.length())
+    }
+
+    fn compressed_slice<'a>(&self, gltf: &Gltf, buffer_data: &'a [Vec<u8>]) -> &'a [u8] {
+        let view = gltf.views().nth(self.link.buffer_view).unwrap();
+        &buffer_data[view.buffer().index()][view.offset()..view.offset() + view.length()]
+    }
+
+    // Caches only the raw Draco decode, not the synthesized tangent buffer: a decode
+    // result is shared (by `bufferView`) across every primitive that points at it, but
+    // whether a TANGENT needs synthesizing is a per-primitive question (it depends on
+    // that primitive's own attribute map), so it's decided fresh per primitive in
+    // `attach_tangent_buffer` instead of being baked into the cached data.
+    pub fn decode_mesh(&self, gltf: &Gltf, buffer_data: &[Vec<u8>]) -> Option<(DracoDecodeConfig, Vec<u8>)> {
+        let result_opt = decode_mesh_with_config_sync(self.compressed_slice(gltf, buffer_data));
 
         let Some(result) = result_opt else {
             warn!("draco decode fail!");
             return None;
         };
 
-        Some((result.config, vec![result.data]))
+        Some((result.config, result.data))
+    }
+
+    pub fn attach_tangent_buffer(
+        &self,
+        primitive: &Primitive,
+        config: &DracoDecodeConfig,
+        base_data: &[u8],
+    ) -> Vec<Vec<u8>> {
+        let mut data = vec![base_data.to_vec()];
+        if let Some(tangents) = self.generate_tangent_buffer(primitive, config, base_data) {
+            data.push(tangents);
+        }
+        data
+    }
+
+    fn tangent_attribute_ids(&self) -> Option<(usize, usize, usize)> {
+        let mut position = None;
+        let mut normal = None;
+        let mut uv0 = None;
+        for (id, semantic) in &self.link.map {
+            match semantic {
+                Semantic::Positions => position = Some(*id),
+                Semantic::Normals => normal = Some(*id),
+                Semantic::TexCoords(0) => uv0 = Some(*id),
+                Semantic::Tangents => return None,
+                _ => {}
+            }
+        }
+        Some((position?, normal?, uv0?))
+    }
+
+    fn generate_tangent_buffer(
+        &self,
+        primitive: &Primitive,
+        config: &DracoDecodeConfig,
+        decoded: &[u8],
+    ) -> Option<Vec<u8>> {
+        let (position_id, normal_id, uv_id) = self.tangent_attribute_ids()?;
+
+        let is_f32 = |semantic: &Semantic, dimensions: gltf::accessor::Dimensions| {
+            let accessor = primitive.get(semantic)?;
+            (accessor.data_type() == gltf::accessor::DataType::F32
+                && accessor.dimensions() == dimensions)
+                .then_some(())
+        };
+        is_f32(&Semantic::Positions, gltf::accessor::Dimensions::Vec3)?;
+        is_f32(&Semantic::Normals, gltf::accessor::Dimensions::Vec3)?;
+        is_f32(&Semantic::TexCoords(0), gltf::accessor::Dimensions::Vec2)?;
+
+        let attributes = config.attributes();
+        let positions: Vec<[f32; 3]> = read_f32_array(decoded, &attributes[position_id]);
+        let normals: Vec<[f32; 3]> = read_f32_array(decoded, &attributes[normal_id]);
+        let uvs: Vec<[f32; 2]> = read_f32_array(decoded, &attributes[uv_id]);
+
+        let indices = primitive.indices()?;
+        let index_values =
+            read_indices(decoded, promoted_index_data_type(&indices), indices.count())?;
+
+        let tangents = compute_tangents(&index_values, &positions, &normals, &uvs);
+
+        let mut bytes = Vec::with_capacity(tangents.len() * 16);
+        for tangent in &tangents {
+            for component in tangent {
+                bytes.extend_from_slice(&component.to_le_bytes());
+            }
+        }
+        Some(bytes)
+    }
+}
+
+pub type DracoDecodeCacheKey = (usize, usize, usize);
+
+fn read_f32_array<const N: usize>(decoded: &[u8], attribute: &AttributeDataType) -> Vec<[f32; N]> {
+    decoded[attribute.offset()..attribute.offset() + attribute.lenght()]
+        .chunks_exact(N * 4)
+        .map(|chunk| {
+            let mut out = [0.0_f32; N];
+            for (component, bytes) in out.iter_mut().zip(chunk.chunks_exact(4)) {
+                *component = f32::from_le_bytes(bytes.try_into().unwrap());
+            }
+            out
+        })
+        .collect()
+}
+
+fn read_indices(decoded: &[u8], data_type: gltf::accessor::DataType, count: usize) -> Option<Vec<u32>> {
+    match data_type {
+        gltf::accessor::DataType::U8 => {
+            Some(decoded[..count].iter().map(|&b| b as u32).collect())
+        }
+        gltf::accessor::DataType::U16 => Some(
+            decoded[..count * 2]
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes(c.try_into().unwrap()) as u32)
+                .collect(),
+        ),
+        gltf::accessor::DataType::U32 => Some(
+            decoded[..count * 4]
+                .chunks_exact(4)
+                .map(|c| u32::from_le_bytes(c.try_into().unwrap()))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+// The U16->U32 promotion `build_document` applies to the index accessor when
+// `count > u16::MAX`; `generate_tangent_buffer` uses it too, so the decoded index
+// buffer is read at the width it was actually written at.
+fn promoted_index_data_type(indices: &gltf::Accessor) -> gltf::accessor::DataType {
+    match (indices.data_type(), indices.count()) {
+        (gltf::accessor::DataType::U16, count) if count > u16::MAX as usize => {
+            gltf::accessor::DataType::U32
+        }
+        (data_type, _) => data_type,
+    }
+}
+
+// Copies the primitive's material, and the textures/images/samplers it references,
+// into this synthetic `Root`, so the rebuilt primitive doesn't depend on the main
+// loader re-resolving an index against the source document's (absent-here) materials.
+// Returns `None` when the primitive has no explicit material.
+fn push_material(
+    root: &mut gltf::json::Root,
+    material: &gltf::Material,
+    buffer_data: &[Vec<u8>],
+) -> Option<gltf::json::Index<gltf::json::Material>> {
+    material.index()?;
+
+    let pbr = material.pbr_metallic_roughness();
+    let material_json = gltf::json::Material {
+        pbr_metallic_roughness: gltf::json::material::PbrMetallicRoughness {
+            base_color_factor: gltf::json::material::PbrBaseColorFactor(pbr.base_color_factor()),
+            base_color_texture: pbr
+                .base_color_texture()
+                .and_then(|info| push_texture_info(root, &info, buffer_data)),
+            metallic_factor: gltf::json::material::StrengthFactor(pbr.metallic_factor()),
+            roughness_factor: gltf::json::material::StrengthFactor(pbr.roughness_factor()),
+            metallic_roughness_texture: pbr
+                .metallic_roughness_texture()
+                .and_then(|info| push_texture_info(root, &info, buffer_data)),
+            extensions: Default::default(),
+            extras: Default::default(),
+        },
+        normal_texture: material.normal_texture().and_then(|info| {
+            Some(gltf::json::material::NormalTexture {
+                index: push_texture(root, &info.texture(), buffer_data)?,
+                tex_coord: info.tex_coord(),
+                scale: info.scale(),
+                extensions: Default::default(),
+                extras: Default::default(),
+            })
+        }),
+        occlusion_texture: material.occlusion_texture().and_then(|info| {
+            Some(gltf::json::material::OcclusionTexture {
+                index: push_texture(root, &info.texture(), buffer_data)?,
+                tex_coord: info.tex_coord(),
+                strength: gltf::json::material::StrengthFactor(info.strength()),
+                extensions: Default::default(),
+                extras: Default::default(),
+            })
+        }),
+        emissive_texture: material
+            .emissive_texture()
+            .and_then(|info| push_texture_info(root, &info, buffer_data)),
+        emissive_factor: gltf::json::material::EmissiveFactor(material.emissive_factor()),
+        alpha_cutoff: material.alpha_cutoff().map(gltf::json::material::AlphaCutoff),
+        alpha_mode: Valid(material.alpha_mode()),
+        double_sided: material.double_sided(),
+        name: None,
+        extensions: Default::default(),
+        extras: Default::default(),
+    };
+
+    Some(root.push(material_json))
+}
+
+fn push_texture_info(
+    root: &mut gltf::json::Root,
+    info: &gltf::texture::Info,
+    buffer_data: &[Vec<u8>],
+) -> Option<gltf::json::texture::Info> {
+    Some(gltf::json::texture::Info {
+        index: push_texture(root, &info.texture(), buffer_data)?,
+        tex_coord: info.tex_coord(),
+        extensions: Default::default(),
+        extras: Default::default(),
+    })
+}
+
+fn push_texture(
+    root: &mut gltf::json::Root,
+    texture: &gltf::Texture,
+    buffer_data: &[Vec<u8>],
+) -> Option<gltf::json::Index<gltf::json::Texture>> {
+    let source = push_image(root, &texture.source(), buffer_data)?;
+    let sampler = texture
+        .sampler()
+        .index()
+        .map(|_| push_sampler(root, &texture.sampler()));
+    Some(root.push(gltf::json::Texture {
+        sampler,
+        source,
+        name: None,
+        extensions: Default::default(),
+        extras: Default::default(),
+    }))
+}
+
+fn push_sampler(
+    root: &mut gltf::json::Root,
+    sampler: &gltf::texture::Sampler,
+) -> gltf::json::Index<gltf::json::texture::Sampler> {
+    root.push(gltf::json::texture::Sampler {
+        mag_filter: sampler.mag_filter().map(Valid),
+        min_filter: sampler.min_filter().map(Valid),
+        wrap_s: Valid(sampler.wrap_s()),
+        wrap_t: Valid(sampler.wrap_t()),
+        name: None,
+        extensions: Default::default(),
+        extras: Default::default(),
+    })
+}
+
+// Only images referenced by URI can be copied without also plumbing their raw bytes
+// through to the loader's `out_data` buffer list (which `build_document` doesn't have
+// access to); GLB-style images embedded in a `bufferView` are skipped rather than
+// produce a buffer index with no matching bytes.
+fn push_image(
+    root: &mut gltf::json::Root,
+    image: &gltf::Image,
+    _buffer_data: &[Vec<u8>],
+) -> Option<gltf::json::Index<gltf::json::Image>> {
+    match image.source() {
+        gltf::image::Source::Uri { uri, mime_type } => Some(root.push(gltf::json::Image {
+            buffer_view: None,
+            mime_type: mime_type.map(|m| gltf::json::image::MimeType(m.to_string())),
+            uri: Some(uri.to_string()),
+            name: None,
+            extensions: Default::default(),
+            extras: Default::default(),
+        })),
+        gltf::image::Source::View { .. } => None,
     }
 }
+
+fn sub3(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn add3(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn scale3(a: [f32; 3], s: f32) -> [f32; 3] {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+fn dot3(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross3(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn normalize3(a: [f32; 3]) -> [f32; 3] {
+    let len = dot3(a, a).sqrt();
+    if len > f32::EPSILON {
+        scale3(a, 1.0 / len)
+    } else {
+        [1.0, 0.0, 0.0]
+    }
+}
+
+// Lengyel's method: accumulate each triangle's tangent/bitangent into its vertices,
+// then Gram-Schmidt orthogonalize against the normal and store the handedness (the
+// sign of dot(cross(n, t), bitangent)) as w.
+fn compute_tangents(
+    indices: &[u32],
+    positions: &[[f32; 3]],
+    normals: &[[f32; 3]],
+    uvs: &[[f32; 2]],
+) -> Vec<[f32; 4]> {
+    let vertex_count = positions.len();
+    let mut tangent_accum = vec![[0.0_f32; 3]; vertex_count];
+    let mut bitangent_accum = vec![[0.0_f32; 3]; vertex_count];
+
+    for triangle in indices.chunks_exact(3) {
+        let (i0, i1, i2) = (triangle[0] as usize, triangle[1] as usize, triangle[2] as usize);
+        let (p0, p1, p2) = (positions[i0], positions[i1], positions[i2]);
+        let (uv0, uv1, uv2) = (uvs[i0], uvs[i1], uvs[i2]);
+
+        let e1 = sub3(p1, p0);
+        let e2 = sub3(p2, p0);
+        let duv1 = [uv1[0] - uv0[0], uv1[1] - uv0[1]];
+        let duv2 = [uv2[0] - uv0[0], uv2[1] - uv0[1]];
+
+        let denom = duv1[0] * duv2[1] - duv2[0] * duv1[1];
+        if denom == 0.0 {
+            continue;
+        }
+        let r = 1.0 / denom;
+        let tangent = scale3(sub3(scale3(e1, duv2[1]), scale3(e2, duv1[1])), r);
+        let bitangent = scale3(sub3(scale3(e2, duv1[0]), scale3(e1, duv2[0])), r);
+
+        for &i in &[i0, i1, i2] {
+            tangent_accum[i] = add3(tangent_accum[i], tangent);
+            bitangent_accum[i] = add3(bitangent_accum[i], bitangent);
+        }
+    }
+
+    (0..vertex_count)
+        .map(|i| {
+            let normal = normals[i];
+            let tangent = sub3(tangent_accum[i], scale3(normal, dot3(normal, tangent_accum[i])));
+            let tangent = normalize3(tangent);
+            let handedness = if dot3(cross3(normal, tangent), bitangent_accum[i]) < 0.0 {
+                -1.0
+            } else {
+                1.0
+            };
+            [tangent[0], tangent[1], tangent[2], handedness]
+        })
+        .collect()
+}
@@ -1,24 +1,37 @@
+use std::sync::{Arc, Mutex};
+
 use bevy_app::{App, Plugin};
 use bevy_asset::LoadContext;
-#[cfg(not(target_family = "wasm"))]
 use bevy_gltf::extensions::GltfExtensionHandlers;
 use bevy_gltf::{
     extensions::GltfExtensionHandler,
     gltf::{Document, Gltf as JsonGltf, Primitive},
 };
+use bevy_platform::collections::HashMap;
+use draco_decoder::DracoDecodeConfig;
 
-use crate::khr_draco_mesh_compression::DracoExtension;
+use crate::khr_draco_mesh_compression::{DracoDecodeCacheKey, DracoExtension};
 
 mod khr_draco_mesh_compression;
 
-#[derive(Default, Clone)]
-struct GltfDracoDecoderExtensionHandler;
+type DracoDecodeCache = Arc<Mutex<HashMap<DracoDecodeCacheKey, Arc<(DracoDecodeConfig, Vec<u8>)>>>>;
+
+#[derive(Default)]
+struct GltfDracoDecoderExtensionHandler {
+    decode_cache: DracoDecodeCache,
+}
 
 impl GltfExtensionHandler for GltfDracoDecoderExtensionHandler {
+    // `DracoDecodeCacheKey` is only unique within a single glTF document, so each
+    // load needs its own cache rather than sharing one `Arc` across every clone.
     fn dyn_clone(&self) -> Box<dyn GltfExtensionHandler> {
-        Box::new((*self).clone())
+        Box::new(Self::default())
     }
 
+    // `GltfExtensionHandler::on_gltf_primitive` is a synchronous hook with no way to
+    // defer `out_doc`/`out_data`, so the Draco decode below still runs on wasm's single
+    // JS thread for the duration of every compressed primitive; there's no async path
+    // to opt into until the trait itself supports yielding mid-callback.
     fn on_gltf_primitive(
         &mut self,
         load_context: &mut LoadContext<'_>,
@@ -28,12 +41,26 @@ impl GltfExtensionHandler for GltfDracoDecoderExtensionHandler {
         out_doc: &mut Option<Document>,
         out_data: &mut Option<Vec<Vec<u8>>>,
     ) {
-        if let Some(draco_ext) =
-            DracoExtension::parse(load_context, &gltf_json, gltf_primitive).as_mut()
-            && let Some((config, decode_data)) = draco_ext.decode_mesh(gltf_json, &buffer_data)
-        {
-            *out_data = Some(decode_data);
-            *out_doc = draco_ext.build_document(&gltf_primitive, &config);
+        let Some(draco_ext) = DracoExtension::parse(load_context, &gltf_json, gltf_primitive)
+        else {
+            return;
+        };
+        let key = draco_ext.cache_key(gltf_json);
+
+        let cached = self.decode_cache.lock().unwrap().get(&key).cloned();
+        let decoded = match cached {
+            Some(decoded) => Some(decoded),
+            None => draco_ext.decode_mesh(gltf_json, buffer_data).map(|result| {
+                let result = Arc::new(result);
+                self.decode_cache.lock().unwrap().insert(key, result.clone());
+                result
+            }),
+        };
+
+        if let Some(decoded) = decoded {
+            let (config, base_data) = &*decoded;
+            *out_data = Some(draco_ext.attach_tangent_buffer(gltf_primitive, config, base_data));
+            *out_doc = draco_ext.build_document(&gltf_primitive, config, buffer_data);
         }
     }
 }
@@ -42,11 +69,20 @@ pub struct GltfDracoDecoderPlugin;
 
 impl Plugin for GltfDracoDecoderPlugin {
     fn build(&self, app: &mut App) {
+        #[cfg(target_family = "wasm")]
+        bevy_tasks::block_on(async {
+            app.world_mut()
+                .resource_mut::<GltfExtensionHandlers>()
+                .0
+                .write()
+                .await
+                .push(Box::new(GltfDracoDecoderExtensionHandler::default()));
+        });
         #[cfg(not(target_family = "wasm"))]
         app.world_mut()
             .resource_mut::<GltfExtensionHandlers>()
             .0
             .write_blocking()
-            .push(Box::new(GltfDracoDecoderExtensionHandler));
+            .push(Box::new(GltfDracoDecoderExtensionHandler::default()));
     }
 }